@@ -6,223 +6,472 @@
 
 use absy::variable::Variable;
 use std::collections::HashMap;
+use std::fmt;
 use field::Field;
 use typed_absy::*;
 
+#[derive(Debug, PartialEq)]
+pub struct PropagationError(String);
+
+impl PropagationError {
+	fn new<S: Into<String>>(message: S) -> Self {
+		PropagationError(message.into())
+	}
+}
+
+impl fmt::Display for PropagationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
 pub trait Propagate<T: Field> {
-	fn propagate(self, functions: &Vec<TypedFunction<T>>) -> Self;
+	fn propagate(self, functions: &Vec<TypedFunction<T>>) -> Result<Self, PropagationError> where Self: Sized;
 }
 
 pub trait PropagateWithContext<T: Field> {
-	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> Self;
+	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> Result<Self, PropagationError> where Self: Sized;
+}
+
+fn is_constant<T: Field>(e: &TypedExpression<T>) -> bool {
+	match e {
+		TypedExpression::FieldElement(FieldElementExpression::Number(..)) => true,
+		TypedExpression::FieldElement(_) => false,
+		TypedExpression::Boolean(BooleanExpression::Value(..)) => true,
+		TypedExpression::Boolean(_) => false,
+		TypedExpression::Array(ArrayExpression::Value(elements)) => elements.iter().all(|e| is_constant(e)),
+		TypedExpression::Array(ArrayExpression::Identifier(..)) => false,
+		TypedExpression::Select(..) => false
+	}
+}
+
+// a constant `FieldElementExpression::Number` used as an array index, turned into a `usize`
+fn field_to_usize<T: Field>(n: &T) -> Result<usize, PropagationError> {
+	format!("{}", n).parse().map_err(|_| PropagationError::new(format!("array index out of range: {} does not fit in a usize", n)))
+}
+
+// guards against unbounded recursion when a constant-argument call inlines into itself
+// (directly or mutually); past this depth we just leave the call un-inlined rather than
+// overflow the stack
+const MAX_INLINE_DEPTH: usize = 64;
+
+thread_local! {
+	static INLINE_DEPTH: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+struct InlineDepthGuard;
+
+impl InlineDepthGuard {
+	fn enter() -> Option<Self> {
+		INLINE_DEPTH.with(|depth| {
+			if depth.get() >= MAX_INLINE_DEPTH {
+				None
+			} else {
+				depth.set(depth.get() + 1);
+				Some(InlineDepthGuard)
+			}
+		})
+	}
+}
+
+impl Drop for InlineDepthGuard {
+	fn drop(&mut self) {
+		INLINE_DEPTH.with(|depth| depth.set(depth.get() - 1));
+	}
+}
+
+// try to fully evaluate a call to `id` with already-propagated `arguments`, returning the
+// literal return values if the callee's body collapses to constants, `None` if it doesn't.
+// a statically-unsatisfiable condition reached through the inlined body is a genuine compile
+// error and is propagated as `Err`, not swallowed into `None`.
+fn inline_constant_call<T: Field>(id: &str, arguments: &[TypedExpression<T>], functions: &Vec<TypedFunction<T>>) -> Result<Option<Vec<TypedExpression<T>>>, PropagationError> {
+	if !arguments.iter().all(|a| is_constant(a)) {
+		return Ok(None);
+	}
+
+	let callee = match functions.iter().find(|f| f.id == id && f.arguments.len() == arguments.len()) {
+		Some(callee) => callee,
+		None => return Ok(None)
+	};
+
+	// bail out rather than recurse without bound on a constant self-recursive (or mutually
+	// recursive) call; leaving the call un-inlined is always a safe fallback
+	let _depth_guard = match InlineDepthGuard::enter() {
+		Some(guard) => guard,
+		None => return Ok(None)
+	};
+
+	// bind the declared parameters, not whatever happens to be declared first in the body
+	let mut constants: HashMap<Variable, TypedExpression<T>> = callee.arguments.iter()
+		.map(|p| p.id.clone())
+		.zip(arguments.iter().cloned())
+		.collect();
+
+	let mut returns = None;
+
+	for s in callee.statements.clone() {
+		if let Some(s) = s.propagate(&mut constants, functions)? {
+			// once we hit a `return`, anything after it is unreachable: don't evaluate
+			// statically-dead statements, which may be ill-defined (e.g. a division by zero)
+			if let TypedStatement::Return(expressions) = s {
+				returns = Some(expressions);
+				break;
+			}
+		}
+	}
+
+	Ok(match returns {
+		Some(expressions) if expressions.iter().all(|e| is_constant(e)) => Some(expressions),
+		_ => None
+	})
 }
 
 impl<T: Field> PropagateWithContext<T> for TypedExpression<T> {
-	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> TypedExpression<T> {
+	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> Result<TypedExpression<T>, PropagationError> {
 		match self {
-			TypedExpression::FieldElement(e) => e.propagate(constants, functions).into(),
-			TypedExpression::Boolean(e) => e.propagate(constants, functions).into(),
+			TypedExpression::FieldElement(e) => Ok(e.propagate(constants, functions)?.into()),
+			TypedExpression::Boolean(e) => Ok(e.propagate(constants, functions)?.into()),
+			TypedExpression::Array(e) => Ok(TypedExpression::Array(e.propagate(constants, functions)?)),
+			TypedExpression::Select(box array, box index) => {
+				let array = array.propagate(constants, functions)?;
+				let index = index.propagate(constants, functions)?;
+
+				match (array, index) {
+					(ArrayExpression::Value(elements), FieldElementExpression::Number(n)) => {
+						let len = elements.len();
+						let i = field_to_usize(&n)?;
+
+						elements.into_iter().nth(i).ok_or_else(|| PropagationError::new(format!("array index out of bounds: index {} but array has size {}", i, len)))
+					},
+					(array, index) => Ok(TypedExpression::Select(box array, box index))
+				}
+			}
 		}
 	}
 }
 
 impl<T: Field> PropagateWithContext<T> for FieldElementExpression<T> {
-	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> FieldElementExpression<T> {
+	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> Result<FieldElementExpression<T>, PropagationError> {
 		match self {
-			FieldElementExpression::Number(n) => FieldElementExpression::Number(n),
+			FieldElementExpression::Number(n) => Ok(FieldElementExpression::Number(n)),
 			FieldElementExpression::Identifier(id) => {
-				match constants.get(&Variable::field_element(id.clone())) {
+				Ok(match constants.get(&Variable::field_element(id.clone())) {
 					Some(e) => match e {
 						TypedExpression::FieldElement(e) => e.clone(),
 						_ => panic!("")
 					},
 					None => FieldElementExpression::Identifier(id)
-				}
+				})
 			},
 			FieldElementExpression::Add(box e1, box e2) => {
-				match (e1.propagate(constants, functions), e2.propagate(constants, functions)) {
-					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => FieldElementExpression::Number(n1 + n2),
-					(e1, e2) => FieldElementExpression::Add(box e1, box e2),
+				match (e1.propagate(constants, functions)?, e2.propagate(constants, functions)?) {
+					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => Ok(FieldElementExpression::Number(n1 + n2)),
+					// e + 0 => e, 0 + e => e
+					(FieldElementExpression::Number(ref n), e) | (e, FieldElementExpression::Number(ref n)) if *n == T::from(0) => Ok(e),
+					(e1, e2) => Ok(FieldElementExpression::Add(box e1, box e2)),
 				}
 			},
 			FieldElementExpression::Sub(box e1, box e2) => {
-				match (e1.propagate(constants, functions), e2.propagate(constants, functions)) {
-					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => FieldElementExpression::Number(n1 - n2),
-					(e1, e2) => FieldElementExpression::Sub(box e1, box e2),
+				match (e1.propagate(constants, functions)?, e2.propagate(constants, functions)?) {
+					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => Ok(FieldElementExpression::Number(n1 - n2)),
+					// e - 0 => e
+					(e, FieldElementExpression::Number(ref n)) if *n == T::from(0) => Ok(e),
+					(e1, e2) => Ok(FieldElementExpression::Sub(box e1, box e2)),
 				}
 			},
 			FieldElementExpression::Mult(box e1, box e2) => {
-				match (e1.propagate(constants, functions), e2.propagate(constants, functions)) {
-					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => FieldElementExpression::Number(n1 * n2),
-					(e1, e2) => FieldElementExpression::Mult(box e1, box e2),
+				match (e1.propagate(constants, functions)?, e2.propagate(constants, functions)?) {
+					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => Ok(FieldElementExpression::Number(n1 * n2)),
+					// e * 0 => 0, 0 * e => 0
+					(FieldElementExpression::Number(ref n), _) | (_, FieldElementExpression::Number(ref n)) if *n == T::from(0) => Ok(FieldElementExpression::Number(T::from(0))),
+					// e * 1 => e, 1 * e => e
+					(FieldElementExpression::Number(ref n), e) | (e, FieldElementExpression::Number(ref n)) if *n == T::from(1) => Ok(e),
+					(e1, e2) => Ok(FieldElementExpression::Mult(box e1, box e2)),
 				}
 			},
 			FieldElementExpression::Div(box e1, box e2) => {
-				match (e1.propagate(constants, functions), e2.propagate(constants, functions)) {
-					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => FieldElementExpression::Number(n1 / n2),
-					(e1, e2) => FieldElementExpression::Div(box e1, box e2),
+				match (e1.propagate(constants, functions)?, e2.propagate(constants, functions)?) {
+					// division is multiplication by the modular inverse, which is undefined for a zero divisor,
+					// regardless of whether the numerator is itself constant
+					(_, FieldElementExpression::Number(ref n2)) if *n2 == T::from(0) => {
+						Err(PropagationError::new("cannot divide by a constant zero"))
+					},
+					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => Ok(FieldElementExpression::Number(n1 / n2)),
+					// e / 1 => e
+					(e, FieldElementExpression::Number(ref n)) if *n == T::from(1) => Ok(e),
+					(e1, e2) => Ok(FieldElementExpression::Div(box e1, box e2)),
 				}
 			},
 			FieldElementExpression::Pow(box e1, box e2) => {
-				match (e1.propagate(constants, functions), e2.propagate(constants, functions)) {
-					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => FieldElementExpression::Number(n1.pow(n2)),
-					(e1, e2) => FieldElementExpression::Pow(box e1, box e2),
+				match (e1.propagate(constants, functions)?, e2.propagate(constants, functions)?) {
+					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => Ok(FieldElementExpression::Number(n1.pow(n2))),
+					// e.pow(0) => 1
+					(_, FieldElementExpression::Number(ref n)) if *n == T::from(0) => Ok(FieldElementExpression::Number(T::from(1))),
+					// e.pow(1) => e
+					(e, FieldElementExpression::Number(ref n)) if *n == T::from(1) => Ok(e),
+					(e1, e2) => Ok(FieldElementExpression::Pow(box e1, box e2)),
 				}
 			},
 			FieldElementExpression::IfElse(box condition, box consequence, box alternative) => {
-				let consequence = consequence.propagate(constants, functions);
-				let alternative = alternative.propagate(constants, functions);
-				match condition.propagate(constants, functions) {
-					BooleanExpression::Value(true) => consequence,
-					BooleanExpression::Value(false) => alternative,
-					c => FieldElementExpression::IfElse(box c, box consequence, box alternative) 
+				let consequence = consequence.propagate(constants, functions)?;
+				let alternative = alternative.propagate(constants, functions)?;
+				match condition.propagate(constants, functions)? {
+					BooleanExpression::Value(true) => Ok(consequence),
+					BooleanExpression::Value(false) => Ok(alternative),
+					c => Ok(FieldElementExpression::IfElse(box c, box consequence, box alternative))
 				}
 			},
 			FieldElementExpression::FunctionCall(id, arguments) => {
-				let arguments = arguments.into_iter().map(|a| a.propagate(constants, functions)).collect();
-				FieldElementExpression::FunctionCall(id, arguments)
+				let arguments: Vec<TypedExpression<T>> = arguments.into_iter().map(|a| a.propagate(constants, functions)).collect::<Result<Vec<_>, _>>()?;
+
+				match inline_constant_call(&id, &arguments, functions)? {
+					Some(mut returns) if returns.len() == 1 => match returns.remove(0) {
+						TypedExpression::FieldElement(e) => Ok(e),
+						_ => Ok(FieldElementExpression::FunctionCall(id, arguments))
+					},
+					_ => Ok(FieldElementExpression::FunctionCall(id, arguments))
+				}
 			}
 		}
 	}
 }
 
 impl<T: Field> PropagateWithContext<T> for BooleanExpression<T> {
-	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> BooleanExpression<T> {
+	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> Result<BooleanExpression<T>, PropagationError> {
 		match self {
-			BooleanExpression::Value(v) => BooleanExpression::Value(v),
+			BooleanExpression::Value(v) => Ok(BooleanExpression::Value(v)),
 			BooleanExpression::Identifier(id) => {
-				match constants.get(&Variable::boolean(id.clone())) {
+				Ok(match constants.get(&Variable::boolean(id.clone())) {
 					Some(e) => match e {
 						TypedExpression::Boolean(e) => e.clone(),
 						_ => panic!("")
 					},
 					None => BooleanExpression::Identifier(id)
-				}
+				})
 			},
 			BooleanExpression::Eq(box e1, box e2) => {
-				let e1 = e1.propagate(constants, functions);
-				let e2 = e2.propagate(constants, functions);
+				let e1 = e1.propagate(constants, functions)?;
+				let e2 = e2.propagate(constants, functions)?;
 
 				match (e1, e2) {
 					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
-						BooleanExpression::Value(n1 == n2)
+						Ok(BooleanExpression::Value(n1 == n2))
 					}
-					(e1, e2) => BooleanExpression::Eq(box e1, box e2)
+					(e1, e2) => Ok(BooleanExpression::Eq(box e1, box e2))
 				}
 			}
 			BooleanExpression::Lt(box e1, box e2) => {
-				let e1 = e1.propagate(constants, functions);
-				let e2 = e2.propagate(constants, functions);
+				let e1 = e1.propagate(constants, functions)?;
+				let e2 = e2.propagate(constants, functions)?;
 
 				match (e1, e2) {
 					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
-						BooleanExpression::Value(n1 < n2)
+						Ok(BooleanExpression::Value(n1 < n2))
 					}
-					(e1, e2) => BooleanExpression::Lt(box e1, box e2)
+					(e1, e2) => Ok(BooleanExpression::Lt(box e1, box e2))
 				}
 			}
 			BooleanExpression::Le(box e1, box e2) => {
-				let e1 = e1.propagate(constants, functions);
-				let e2 = e2.propagate(constants, functions);
+				let e1 = e1.propagate(constants, functions)?;
+				let e2 = e2.propagate(constants, functions)?;
 
 				match (e1, e2) {
 					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
-						BooleanExpression::Value(n1 <= n2)
+						Ok(BooleanExpression::Value(n1 <= n2))
 					}
-					(e1, e2) => BooleanExpression::Le(box e1, box e2)
+					(e1, e2) => Ok(BooleanExpression::Le(box e1, box e2))
 				}
 			}
 			BooleanExpression::Gt(box e1, box e2) => {
-				let e1 = e1.propagate(constants, functions);
-				let e2 = e2.propagate(constants, functions);
+				let e1 = e1.propagate(constants, functions)?;
+				let e2 = e2.propagate(constants, functions)?;
 
 				match (e1, e2) {
 					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
-						BooleanExpression::Value(n1 > n2)
+						Ok(BooleanExpression::Value(n1 > n2))
 					}
-					(e1, e2) => BooleanExpression::Gt(box e1, box e2)
+					(e1, e2) => Ok(BooleanExpression::Gt(box e1, box e2))
 				}
 			}
 			BooleanExpression::Ge(box e1, box e2) => {
-				let e1 = e1.propagate(constants, functions);
-				let e2 = e2.propagate(constants, functions);
+				let e1 = e1.propagate(constants, functions)?;
+				let e2 = e2.propagate(constants, functions)?;
 
 				match (e1, e2) {
 					(FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
-						BooleanExpression::Value(n1 >= n2)
+						Ok(BooleanExpression::Value(n1 >= n2))
 					}
-					(e1, e2) => BooleanExpression::Ge(box e1, box e2)
+					(e1, e2) => Ok(BooleanExpression::Ge(box e1, box e2))
+				}
+			}
+			BooleanExpression::And(box e1, box e2) => {
+				let e1 = e1.propagate(constants, functions)?;
+				let e2 = e2.propagate(constants, functions)?;
+
+				match (e1, e2) {
+					(BooleanExpression::Value(v1), BooleanExpression::Value(v2)) => Ok(BooleanExpression::Value(v1 && v2)),
+					(BooleanExpression::Value(false), _) | (_, BooleanExpression::Value(false)) => Ok(BooleanExpression::Value(false)),
+					(BooleanExpression::Value(true), e) | (e, BooleanExpression::Value(true)) => Ok(e),
+					(e1, e2) => Ok(BooleanExpression::And(box e1, box e2))
+				}
+			}
+			BooleanExpression::Or(box e1, box e2) => {
+				let e1 = e1.propagate(constants, functions)?;
+				let e2 = e2.propagate(constants, functions)?;
+
+				match (e1, e2) {
+					(BooleanExpression::Value(v1), BooleanExpression::Value(v2)) => Ok(BooleanExpression::Value(v1 || v2)),
+					(BooleanExpression::Value(true), _) | (_, BooleanExpression::Value(true)) => Ok(BooleanExpression::Value(true)),
+					(BooleanExpression::Value(false), e) | (e, BooleanExpression::Value(false)) => Ok(e),
+					(e1, e2) => Ok(BooleanExpression::Or(box e1, box e2))
 				}
 			}
+			BooleanExpression::Not(box e) => {
+				match e.propagate(constants, functions)? {
+					BooleanExpression::Value(v) => Ok(BooleanExpression::Value(!v)),
+					e => Ok(BooleanExpression::Not(box e))
+				}
+			}
+		}
+	}
+}
+
+impl<T: Field> PropagateWithContext<T> for ArrayExpression<T> {
+	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> Result<ArrayExpression<T>, PropagationError> {
+		match self {
+			ArrayExpression::Value(elements) => {
+				let elements = elements.into_iter().map(|e| e.propagate(constants, functions)).collect::<Result<Vec<_>, _>>()?;
+				Ok(ArrayExpression::Value(elements))
+			},
+			ArrayExpression::Identifier(id) => {
+				Ok(match constants.get(&Variable::array(id.clone())) {
+					Some(e) => match e {
+						TypedExpression::Array(e) => e.clone(),
+						_ => panic!("")
+					},
+					None => ArrayExpression::Identifier(id)
+				})
+			}
 		}
 	}
 }
 
 impl<T: Field> TypedExpressionList<T> {
-	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> TypedExpressionList<T> {
+	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> Result<TypedExpressionList<T>, PropagationError> {
 		match self {
 			TypedExpressionList::FunctionCall(id, arguments, types) => {
-				TypedExpressionList::FunctionCall(id, arguments.into_iter().map(|e| e.propagate(constants, functions)).collect(), types)
+				let arguments = arguments.into_iter().map(|e| e.propagate(constants, functions)).collect::<Result<Vec<_>, _>>()?;
+				Ok(TypedExpressionList::FunctionCall(id, arguments, types))
 			}
 		}
 	}
 }
 
 impl<T: Field> TypedStatement<T> {
-	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> Option<TypedStatement<T>> {
+	fn propagate(self, constants: &mut HashMap<Variable, TypedExpression<T>>, functions: &Vec<TypedFunction<T>>) -> Result<Option<TypedStatement<T>>, PropagationError> {
 		match self {
-			TypedStatement::Declaration(v) => Some(TypedStatement::Declaration(v)),
-			TypedStatement::Return(expressions) => Some(TypedStatement::Return(expressions.into_iter().map(|e| e.propagate(constants, functions)).collect())),
+			TypedStatement::Declaration(v) => Ok(Some(TypedStatement::Declaration(v))),
+			TypedStatement::Return(expressions) => {
+				let expressions = expressions.into_iter().map(|e| e.propagate(constants, functions)).collect::<Result<Vec<_>, _>>()?;
+				Ok(Some(TypedStatement::Return(expressions)))
+			},
 			TypedStatement::Definition(var, expr) => {
-				match expr.propagate(constants, functions) {
-					e @ TypedExpression::Boolean(BooleanExpression::Value(..)) | e @ TypedExpression::FieldElement(FieldElementExpression::Number(..)) => {
-						constants.insert(var, e);
-						None
-					},
-					e => {
-						Some(TypedStatement::Definition(var, e))
-					}
+				let e = expr.propagate(constants, functions)?;
+
+				if is_constant(&e) {
+					constants.insert(var, e);
+					Ok(None)
+				} else {
+					Ok(Some(TypedStatement::Definition(var, e)))
 				}
 			},
 			TypedStatement::Condition(e1, e2) => {
-				// could stop execution here if condition is known to fail...
-				Some(TypedStatement::Condition(e1.propagate(constants, functions), e2.propagate(constants, functions)))
+				let e1 = e1.propagate(constants, functions)?;
+				let e2 = e2.propagate(constants, functions)?;
+
+				match (&e1, &e2) {
+					(TypedExpression::FieldElement(FieldElementExpression::Number(n1)), TypedExpression::FieldElement(FieldElementExpression::Number(n2))) => {
+						if n1 == n2 {
+							Ok(None)
+						} else {
+							Err(PropagationError::new(format!("condition is unsatisfiable: {:?} != {:?}", n1, n2)))
+						}
+					},
+					(TypedExpression::Boolean(BooleanExpression::Value(v1)), TypedExpression::Boolean(BooleanExpression::Value(v2))) => {
+						if v1 == v2 {
+							Ok(None)
+						} else {
+							Err(PropagationError::new(format!("condition is unsatisfiable: {:?} != {:?}", v1, v2)))
+						}
+					},
+					_ => Ok(Some(TypedStatement::Condition(e1, e2)))
+				}
 			},
 			TypedStatement::For(..) => panic!("no for expected"),
 			TypedStatement::MultipleDefinition(variables, expression_list) => {
-				let expression_list = expression_list.propagate(constants, functions);
-				Some(TypedStatement::MultipleDefinition(variables, expression_list))
+				let expression_list = expression_list.propagate(constants, functions)?;
+
+				let inlined = match &expression_list {
+					TypedExpressionList::FunctionCall(id, arguments, _) => inline_constant_call(id, arguments, functions)?
+				};
+
+				match inlined {
+					Some(returns) if returns.len() == variables.len() => {
+						for (v, e) in variables.into_iter().zip(returns.into_iter()) {
+							constants.insert(v, e);
+						}
+						Ok(None)
+					},
+					_ => Ok(Some(TypedStatement::MultipleDefinition(variables, expression_list)))
+				}
 			}
 		}
 	}
 }
 
 impl<T: Field> Propagate<T> for TypedFunction<T> {
-	fn propagate(self, functions: &Vec<TypedFunction<T>>) -> TypedFunction<T> {
+	fn propagate(self, functions: &Vec<TypedFunction<T>>) -> Result<TypedFunction<T>, PropagationError> {
 
 		let mut constants = HashMap::new();
+		let mut statements = vec![];
 
-		TypedFunction {
-			statements: self.statements.into_iter().filter_map(|s| s.propagate(&mut constants, functions)).collect(),
-			..self
+		for s in self.statements {
+			if let Some(s) = s.propagate(&mut constants, functions)? {
+				// once we hit a `return`, anything after it is unreachable
+				let is_return = match s {
+					TypedStatement::Return(..) => true,
+					_ => false
+				};
+
+				statements.push(s);
+
+				if is_return {
+					break;
+				}
+			}
 		}
+
+		Ok(TypedFunction {
+			statements,
+			..self
+		})
 	}
 }
 
 impl<T: Field> TypedProg<T> {
-	pub fn propagate(self) -> TypedProg<T> {
+	pub fn propagate(self) -> Result<TypedProg<T>, PropagationError> {
 
 		let mut functions = vec![];
 
 		for f in self.functions {
-			let fun = f.propagate(&mut functions);
+			let fun = f.propagate(&functions)?;
 			functions.push(fun);
 		}
 
-		TypedProg {
+		Ok(TypedProg {
 			functions,
 			..self
-		}
+		})
 	}
 }
 
@@ -230,7 +479,7 @@ impl<T: Field> TypedProg<T> {
 mod tests {
 	use super::*;
 	use field::FieldPrime;
-	
+
 	#[cfg(test)]
 	mod expression {
 		use super::*;
@@ -246,7 +495,7 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(3))
 				);
 
-				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), FieldElementExpression::Number(FieldPrime::from(5)));
+				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), Ok(FieldElementExpression::Number(FieldPrime::from(5))));
 			}
 
 			#[test]
@@ -256,7 +505,7 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(2))
 				);
 
-				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), FieldElementExpression::Number(FieldPrime::from(1)));
+				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), Ok(FieldElementExpression::Number(FieldPrime::from(1))));
 			}
 
 			#[test]
@@ -266,7 +515,7 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(2))
 				);
 
-				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), FieldElementExpression::Number(FieldPrime::from(6)));
+				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), Ok(FieldElementExpression::Number(FieldPrime::from(6))));
 			}
 
 			#[test]
@@ -276,7 +525,7 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(2))
 				);
 
-				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), FieldElementExpression::Number(FieldPrime::from(3)));
+				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), Ok(FieldElementExpression::Number(FieldPrime::from(3))));
 			}
 
 			#[test]
@@ -286,7 +535,7 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(3))
 				);
 
-				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), FieldElementExpression::Number(FieldPrime::from(8)));
+				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), Ok(FieldElementExpression::Number(FieldPrime::from(8))));
 			}
 
 			#[test]
@@ -297,7 +546,7 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(3))
 				);
 
-				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), FieldElementExpression::Number(FieldPrime::from(2)));
+				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), Ok(FieldElementExpression::Number(FieldPrime::from(2))));
 			}
 
 			#[test]
@@ -308,7 +557,102 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(3))
 				);
 
-				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), FieldElementExpression::Number(FieldPrime::from(3)));
+				assert_eq!(e.propagate(&mut HashMap::new(), &mut vec![]), Ok(FieldElementExpression::Number(FieldPrime::from(3))));
+			}
+
+			#[test]
+			fn add_identity() {
+				let id = FieldElementExpression::Identifier(String::from("a"));
+
+				assert_eq!(
+					FieldElementExpression::Add(box id.clone(), box FieldElementExpression::Number(FieldPrime::from(0))).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(id.clone())
+				);
+
+				assert_eq!(
+					FieldElementExpression::Add(box FieldElementExpression::Number(FieldPrime::from(0)), box id.clone()).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(id)
+				);
+			}
+
+			#[test]
+			fn sub_identity() {
+				let id = FieldElementExpression::Identifier(String::from("a"));
+
+				assert_eq!(
+					FieldElementExpression::Sub(box id.clone(), box FieldElementExpression::Number(FieldPrime::from(0))).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(id)
+				);
+			}
+
+			#[test]
+			fn mult_identity_and_annihilator() {
+				let id = FieldElementExpression::Identifier(String::from("a"));
+
+				assert_eq!(
+					FieldElementExpression::Mult(box id.clone(), box FieldElementExpression::Number(FieldPrime::from(1))).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(id.clone())
+				);
+
+				assert_eq!(
+					FieldElementExpression::Mult(box FieldElementExpression::Number(FieldPrime::from(1)), box id.clone()).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(id.clone())
+				);
+
+				assert_eq!(
+					FieldElementExpression::Mult(box id.clone(), box FieldElementExpression::Number(FieldPrime::from(0))).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(FieldElementExpression::Number(FieldPrime::from(0)))
+				);
+
+				assert_eq!(
+					FieldElementExpression::Mult(box FieldElementExpression::Number(FieldPrime::from(0)), box id).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(FieldElementExpression::Number(FieldPrime::from(0)))
+				);
+			}
+
+			#[test]
+			fn div_identity() {
+				let id = FieldElementExpression::Identifier(String::from("a"));
+
+				assert_eq!(
+					FieldElementExpression::Div(box id.clone(), box FieldElementExpression::Number(FieldPrime::from(1))).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(id)
+				);
+			}
+
+			#[test]
+			fn div_by_constant_zero() {
+				let e = FieldElementExpression::Div(
+					box FieldElementExpression::Number(FieldPrime::from(6)),
+					box FieldElementExpression::Number(FieldPrime::from(0))
+				);
+
+				assert!(e.propagate(&mut HashMap::new(), &mut vec![]).is_err());
+			}
+
+			#[test]
+			fn div_symbolic_numerator_by_constant_zero() {
+				let e = FieldElementExpression::Div(
+					box FieldElementExpression::Identifier(String::from("a")),
+					box FieldElementExpression::Number(FieldPrime::from(0))
+				);
+
+				assert!(e.propagate(&mut HashMap::new(), &mut vec![]).is_err());
+			}
+
+			#[test]
+			fn pow_identity() {
+				let id = FieldElementExpression::Identifier(String::from("a"));
+
+				assert_eq!(
+					FieldElementExpression::Pow(box id.clone(), box FieldElementExpression::Number(FieldPrime::from(0))).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(FieldElementExpression::Number(FieldPrime::from(1)))
+				);
+
+				assert_eq!(
+					FieldElementExpression::Pow(box id.clone(), box FieldElementExpression::Number(FieldPrime::from(1))).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(id)
+				);
 			}
 		}
 
@@ -328,8 +672,8 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(2))
 				);
 
-				assert_eq!(e_true.propagate(&mut HashMap::new(), &mut vec![]), BooleanExpression::Value(true));
-				assert_eq!(e_false.propagate(&mut HashMap::new(), &mut vec![]), BooleanExpression::Value(false));
+				assert_eq!(e_true.propagate(&mut HashMap::new(), &mut vec![]), Ok(BooleanExpression::Value(true)));
+				assert_eq!(e_false.propagate(&mut HashMap::new(), &mut vec![]), Ok(BooleanExpression::Value(false)));
 			}
 
 			#[test]
@@ -344,8 +688,8 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(2))
 				);
 
-				assert_eq!(e_true.propagate(&mut HashMap::new(), &mut vec![]), BooleanExpression::Value(true));
-				assert_eq!(e_false.propagate(&mut HashMap::new(), &mut vec![]), BooleanExpression::Value(false));
+				assert_eq!(e_true.propagate(&mut HashMap::new(), &mut vec![]), Ok(BooleanExpression::Value(true)));
+				assert_eq!(e_false.propagate(&mut HashMap::new(), &mut vec![]), Ok(BooleanExpression::Value(false)));
 			}
 
 			#[test]
@@ -360,8 +704,8 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(2))
 				);
 
-				assert_eq!(e_true.propagate(&mut HashMap::new(), &mut vec![]), BooleanExpression::Value(true));
-				assert_eq!(e_false.propagate(&mut HashMap::new(), &mut vec![]), BooleanExpression::Value(false));
+				assert_eq!(e_true.propagate(&mut HashMap::new(), &mut vec![]), Ok(BooleanExpression::Value(true)));
+				assert_eq!(e_false.propagate(&mut HashMap::new(), &mut vec![]), Ok(BooleanExpression::Value(false)));
 			}
 
 			#[test]
@@ -376,8 +720,8 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(5))
 				);
 
-				assert_eq!(e_true.propagate(&mut HashMap::new(), &mut vec![]), BooleanExpression::Value(true));
-				assert_eq!(e_false.propagate(&mut HashMap::new(), &mut vec![]), BooleanExpression::Value(false));
+				assert_eq!(e_true.propagate(&mut HashMap::new(), &mut vec![]), Ok(BooleanExpression::Value(true)));
+				assert_eq!(e_false.propagate(&mut HashMap::new(), &mut vec![]), Ok(BooleanExpression::Value(false)));
 			}
 
 			#[test]
@@ -392,9 +736,171 @@ mod tests {
 					box FieldElementExpression::Number(FieldPrime::from(5))
 				);
 
-				assert_eq!(e_true.propagate(&mut HashMap::new(), &mut vec![]), BooleanExpression::Value(true));
-				assert_eq!(e_false.propagate(&mut HashMap::new(), &mut vec![]), BooleanExpression::Value(false));
+				assert_eq!(e_true.propagate(&mut HashMap::new(), &mut vec![]), Ok(BooleanExpression::Value(true)));
+				assert_eq!(e_false.propagate(&mut HashMap::new(), &mut vec![]), Ok(BooleanExpression::Value(false)));
+			}
+
+			#[test]
+			fn and() {
+				assert_eq!(
+					BooleanExpression::And(box BooleanExpression::Value(true), box BooleanExpression::Value(true)).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Value(true))
+				);
+
+				assert_eq!(
+					BooleanExpression::And(box BooleanExpression::Value(false), box BooleanExpression::Identifier("a".into())).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Value(false))
+				);
+
+				assert_eq!(
+					BooleanExpression::And(box BooleanExpression::Identifier("a".into()), box BooleanExpression::Value(false)).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Value(false))
+				);
+
+				assert_eq!(
+					BooleanExpression::And(box BooleanExpression::Value(true), box BooleanExpression::Identifier("a".into())).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Identifier("a".into()))
+				);
+
+				assert_eq!(
+					BooleanExpression::And(box BooleanExpression::Identifier("a".into()), box BooleanExpression::Value(true)).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Identifier("a".into()))
+				);
 			}
+
+			#[test]
+			fn or() {
+				assert_eq!(
+					BooleanExpression::Or(box BooleanExpression::Value(false), box BooleanExpression::Value(false)).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Value(false))
+				);
+
+				assert_eq!(
+					BooleanExpression::Or(box BooleanExpression::Value(true), box BooleanExpression::Identifier("a".into())).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Value(true))
+				);
+
+				assert_eq!(
+					BooleanExpression::Or(box BooleanExpression::Identifier("a".into()), box BooleanExpression::Value(true)).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Value(true))
+				);
+
+				assert_eq!(
+					BooleanExpression::Or(box BooleanExpression::Value(false), box BooleanExpression::Identifier("a".into())).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Identifier("a".into()))
+				);
+
+				assert_eq!(
+					BooleanExpression::Or(box BooleanExpression::Identifier("a".into()), box BooleanExpression::Value(false)).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Identifier("a".into()))
+				);
+			}
+
+			#[test]
+			fn not() {
+				assert_eq!(
+					BooleanExpression::Not(box BooleanExpression::Value(true)).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Value(false))
+				);
+
+				assert_eq!(
+					BooleanExpression::Not(box BooleanExpression::Value(false)).propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(BooleanExpression::Value(true))
+				);
+			}
+		}
+
+		#[cfg(test)]
+		mod array {
+			use super::*;
+
+			#[test]
+			fn select_constant_index() {
+				let e = TypedExpression::Select(
+					box ArrayExpression::Value(vec![
+						TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(1))),
+						TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(2))),
+						TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(3))),
+					]),
+					box FieldElementExpression::Number(FieldPrime::from(1))
+				);
+
+				assert_eq!(
+					e.propagate(&mut HashMap::new(), &mut vec![]),
+					Ok(TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(2))))
+				);
+			}
+
+			#[test]
+			fn select_out_of_bounds() {
+				let e = TypedExpression::Select(
+					box ArrayExpression::Value(vec![
+						TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(1))),
+						TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(2))),
+					]),
+					box FieldElementExpression::Number(FieldPrime::from(2))
+				);
+
+				assert!(e.propagate(&mut HashMap::new(), &mut vec![]).is_err());
+			}
+
+			#[test]
+			fn select_index_does_not_fit_usize() {
+				// an index literal too large to fit in a `usize` must be a compile error, not a panic
+				let e = TypedExpression::Select(
+					box ArrayExpression::Value(vec![
+						TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(1))),
+					]),
+					box FieldElementExpression::Number(FieldPrime::from("340282366920938463463374607431768211456"))
+				);
+
+				assert!(e.propagate(&mut HashMap::new(), &mut vec![]).is_err());
+			}
+
+			#[test]
+			fn select_through_identifier() {
+				// `field[2] a = [1, 2]; a[1]` once `a` has been folded into `constants`
+				let mut constants = HashMap::new();
+				constants.insert(Variable::array(String::from("a")), TypedExpression::Array(ArrayExpression::Value(vec![
+					TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(1))),
+					TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(2))),
+				])));
+
+				let e = TypedExpression::Select(
+					box ArrayExpression::Identifier(String::from("a")),
+					box FieldElementExpression::Number(FieldPrime::from(1))
+				);
+
+				assert_eq!(
+					e.propagate(&mut constants, &mut vec![]),
+					Ok(TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(2))))
+				);
+			}
+		}
+	}
+
+	#[cfg(test)]
+	mod statement {
+		use super::*;
+
+		#[test]
+		fn condition_holds() {
+			let s = TypedStatement::Condition(
+				TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(2))),
+				TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(2)))
+			);
+
+			assert_eq!(s.propagate(&mut HashMap::new(), &mut vec![]), Ok(None));
+		}
+
+		#[test]
+		fn condition_fails() {
+			let s = TypedStatement::Condition(
+				TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(2))),
+				TypedExpression::FieldElement(FieldElementExpression::Number(FieldPrime::from(3)))
+			);
+
+			assert!(s.propagate(&mut HashMap::new(), &mut vec![]).is_err());
 		}
 	}
-}
\ No newline at end of file
+}